@@ -1,9 +1,13 @@
+extern crate num;
+
 use serialize;
 use serialize::Encodable;
 use std::{i8, i16, i32, i64};
 use std::io::MemWriter;
 use std::io;
 use std::mem::transmute;
+use self::num::bigint::Sign;
+use self::num::{BigInt, BigUint, ToBigInt};
 
 /// An encoder for serializing data to a byte format that preserves lexicographic sort order.
 ///
@@ -24,6 +28,10 @@ use std::mem::transmute;
 /// to 0) will encode into fewer bytes. See `emit_var_u64` for details on serialization
 /// size and format.
 ///
+/// Wrapping a `u8`/`u16`/`u32`/`u64` value in `CompactUint` opts in to a different
+/// variable-length encoding (see `emit_compact_uint`) that shrinks small magnitudes further
+/// than the fixed-width default, at the cost of a coarser length prefix than `emit_var_u64`'s.
+///
 /// ##### Signed Integers
 ///
 /// `i8`, `i16`, `i32`, and `i64` are encoded into 1, 2, 4, and 8 bytes of output, respectively.
@@ -34,6 +42,12 @@ use std::mem::transmute;
 /// to 0) will encode into fewer bytes. See `emit_var_i64` for details on serialization
 /// size and format.
 ///
+/// ##### Arbitrary-Precision Integers
+///
+/// `num::BigInt` and `num::BigUint` are supported for values too large for `i64`/`u64`, via a
+/// sign byte followed by an order-preserving length-prefixed big-endian magnitude. See
+/// `emit_big_int` for details.
+///
 /// ##### Floating Point Numbers
 ///
 /// `f32` and `f64` are encoded into 4 and 8 bytes of output, respectively. Order is preserved
@@ -43,6 +57,10 @@ use std::mem::transmute;
 /// It is typically hard or impossible to use an approximate 'epsilon' approach when using keys for
 /// lookup.
 ///
+/// Wrapping a float in `StrictFloat` opts into a stricter mode that rejects `NAN` with an
+/// `io::IoError` at encode time rather than silently producing a key for it, and canonicalizes
+/// `-0.0` to `+0.0` so the two collapse to a single key. See `StrictFloat` for details.
+///
 /// ##### Characters
 ///
 /// Characters are serialized into between 1 and 4 bytes of output.
@@ -79,17 +97,40 @@ use std::mem::transmute;
 /// added in a backwards-compatible manner, as long as variants are not removed and the variant
 /// order does not change.
 ///
-/// #### Unsupported Data Types
+/// ##### Byte Arrays
+///
+/// The Rust `Encoder`/`Decoder` mechanism makes no distinction between byte arrays and
+/// sequences, so a raw `&[u8]`/`Vec<u8>` cannot be routed to a dedicated encoding just by
+/// implementing `Encodable`. Wrap the bytes in `Bytes` to opt in to a compact, order-preserving
+/// encoding (see `emit_bytes`) instead of the much larger per-item sequence encoding.
+///
+/// ##### Sequences & Maps
 ///
-/// Sequences and maps are unsupported at this time. Sequences and maps could probably be
-/// implemented with a single byte overhead per item, key, and value, but these types are not
-/// typically useful in keys.
+/// Sequences are encoded by prefixing each element with a single "present" marker byte
+/// (`0x01`), and writing a single "terminator" byte (`0x00`) once every element has been
+/// written. Since `0x00 < 0x01`, a sequence which ends earlier always sorts before one that
+/// continues with another element, which gives the correct lexicographic ordering between
+/// variable-length sequences whose elements compare equal up to the shorter sequence's length.
+/// As with strings, elements should not contain embedded null bytes. Unlike a string at the end
+/// of a key, even the final element of a sequence is followed by the terminator byte, so an
+/// embedded null in any element can break sort order.
 ///
-/// Raw byte arrays are unsupported. The Rust `Encoder`/`Decoder` mechanism makes no distinction
-/// between byte arrays and sequences, and thus the overhead for encoding a raw byte array would be
-/// 1 byte per input byte. The theoretical best-case overhead for serializing a raw (null
-/// containing) byte array in order-preserving format is 1 bit per byte, or 9 bytes of output for
-/// every 8 bytes of input.
+/// Maps are encoded the same way, framing each key/value pair as a single element; `BTreeMap`
+/// (rather than a hash-ordered map) should be used so that the encoded bytes are deterministic.
+///
+/// ##### Descending Order
+///
+/// Wrapping a value in `Descending` reverses its sort order, so ascending and descending fields
+/// can be mixed freely within the same tuple or struct key. See `Descending` for details.
+///
+/// `encode_desc` reverses the order of an entire key in one call, equivalent to wrapping the
+/// whole value in `Descending` but taking the value by reference rather than by value.
+///
+/// ##### Text Rendering
+///
+/// `encode_ascii`/`decode_ascii` (and the `encode_hex`/`decode_hex` variants) render an encoded
+/// key as a printable ASCII string whose lexicographic order matches the byte key's order, for
+/// embedding in text-only stores, URLs, or log lines.
 pub struct Encoder<'a> {
     writer: &'a mut io::Writer+'a,
 }
@@ -111,6 +152,120 @@ pub fn encode<'a, T : Encodable<Encoder<'a>, io::IoError>>(object: &T) -> Vec<u8
     writer.unwrap()
 }
 
+/// Encode data using the exact reverse of `encode`'s byte order.
+///
+/// Equivalent to complementing every byte of `encode(object)` -- see `Descending` for why that
+/// reverses the comparison at every position, including for variable-length encodings, and for
+/// the one case (a string with embedded null bytes) where it does not. Useful
+/// as a one-off when the whole key, rather than just one field of a composite key, should sort
+/// in reverse; for mixing ascending and descending fields within the same tuple or struct, wrap
+/// the individual field in `Descending` instead.
+///
+/// #### Usage
+///
+/// ```
+/// # use bytekey::encode_desc;
+/// assert!(encode_desc(&1u32) > encode_desc(&2u32));
+/// ```
+pub fn encode_desc<'a, T : Encodable<Encoder<'a>, io::IoError>>(object: &T) -> Vec<u8> {
+    encode(object).iter().map(|b| !*b).collect()
+}
+
+/// The alphabet used by `encode_ascii`/`decode_ascii`, listed in strictly increasing ASCII
+/// codepoint order. Standard base64's alphabet (`A-Za-z0-9+/`) is not in ascending order and so
+/// cannot be used here.
+static ASCII_ALPHABET: &'static [u8] = &[
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E,
+    0x3F, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x4B, 0x4C, 0x4D,
+    0x4E, 0x4F, 0x50, 0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0x5B, 0x5C,
+    0x5D, 0x5E, 0x5F, 0x60, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0x6A, 0x6B,
+    0x6C, 0x6D, 0x6E, 0x6F,
+];
+
+/// The alphabet used by `encode_hex`/`decode_hex`. `0-9a-f` is already in ascending ASCII order.
+static HEX_ALPHABET: &'static [u8] = b"0123456789abcdef";
+
+fn encode_with_alphabet(bytes: &[u8], alphabet: &[u8], bits_per_symbol: uint) -> String {
+    let mut out = Vec::new();
+    let mut acc: u64 = 0;
+    let mut acc_bits = 0u;
+
+    for &byte in bytes.iter() {
+        acc = (acc << 8) | byte as u64;
+        acc_bits += 8;
+        while acc_bits >= bits_per_symbol {
+            acc_bits -= bits_per_symbol;
+            let idx = (acc >> acc_bits) & ((1 << bits_per_symbol) - 1);
+            out.push(alphabet[idx as uint]);
+        }
+    }
+    if acc_bits > 0 {
+        let idx = (acc << (bits_per_symbol - acc_bits)) & ((1 << bits_per_symbol) - 1);
+        out.push(alphabet[idx as uint]);
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+fn decode_with_alphabet(s: &str, alphabet: &[u8], bits_per_symbol: uint) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut acc: u64 = 0;
+    let mut acc_bits = 0u;
+
+    for &symbol in s.as_bytes().iter() {
+        let idx = alphabet.iter().position(|&a| a == symbol)
+            .expect("invalid symbol for alphabet");
+        acc = (acc << bits_per_symbol) | idx as u64;
+        acc_bits += bits_per_symbol;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push(((acc >> acc_bits) & 0xFF) as u8);
+        }
+    }
+
+    out
+}
+
+/// Render an order-preserving byte key (as produced by `encode`) as a printable ASCII string
+/// whose lexicographic order matches the byte key's order, so keys from this crate can be
+/// embedded in text-only stores, URLs, or log lines without losing sortability.
+///
+/// ##### Encoding
+///
+/// The key's bytes are walked big-endian in 6-bit groups, and each group is mapped through
+/// `ASCII_ALPHABET` (64 symbols, listed in strictly increasing ASCII codepoint order).
+/// `ceil(n * 8 / 6)` symbols are emitted for an `n`-byte key; the low bits of the final, partial group are
+/// zero-padded (there is no `=` padding). Because the underlying bytes are already
+/// order-preserving and big-endian bit comparison reproduces byte comparison, and appending
+/// zero bits is the minimal lexicographic extension, the emitted string's ASCII order equals
+/// the key's byte order.
+///
+/// #### Usage
+///
+/// ```
+/// # use bytekey::{encode, encode_ascii};
+/// assert!(encode_ascii(&1u32) < encode_ascii(&2u32));
+/// ```
+pub fn encode_ascii<'a, T : Encodable<Encoder<'a>, io::IoError>>(object: &T) -> String {
+    encode_with_alphabet(encode(object).as_slice(), ASCII_ALPHABET, 6)
+}
+
+/// The inverse of `encode_ascii`.
+pub fn decode_ascii(s: &str) -> Vec<u8> {
+    decode_with_alphabet(s, ASCII_ALPHABET, 6)
+}
+
+/// Render an order-preserving byte key as a lowercase hex string, using the same
+/// order-preserving scheme as `encode_ascii` with 4-bit groups instead of 6-bit groups.
+pub fn encode_hex<'a, T : Encodable<Encoder<'a>, io::IoError>>(object: &T) -> String {
+    encode_with_alphabet(encode(object).as_slice(), HEX_ALPHABET, 4)
+}
+
+/// The inverse of `encode_hex`.
+pub fn decode_hex(s: &str) -> Vec<u8> {
+    decode_with_alphabet(s, HEX_ALPHABET, 4)
+}
+
 impl<'a> Encoder<'a> {
 
     /// Creates a new ordered bytes encoder whose output will be written to the provided writer.
@@ -307,10 +462,259 @@ impl<'a> Encoder<'a> {
             self.writer.write_be_u64(val ^ mask)
         }
     }
+
+    /// Encode a raw byte slice into order-preserving, self-delimiting output.
+    ///
+    /// ##### Encoding
+    ///
+    /// Each input byte is copied to the output unchanged, except that an embedded `0x00` byte is
+    /// escaped to the two bytes `0x00 0xFF`. A two-byte terminator of `0x00 0x01` is appended
+    /// after the last (possibly escaped) input byte.
+    ///
+    /// Because an escaped embedded null (`0x00 0xFF`) always sorts after the terminator
+    /// (`0x00 0x01`), a byte string that is a proper prefix of another always sorts before it,
+    /// which preserves lexicographic order. Overhead is one byte per embedded null plus the
+    /// two-byte terminator, far better than the 1-byte-per-input-byte cost of routing bytes
+    /// through the sequence encoding.
+    pub fn emit_bytes(&mut self, v: &[u8]) -> EncodeResult {
+        for &byte in v.iter() {
+            try!(self.writer.write_u8(byte));
+            if byte == 0x00 {
+                try!(self.writer.write_u8(0xFF));
+            }
+        }
+        self.writer.write([0x00, 0x01].as_slice())
+    }
+
+    /// Encode an arbitrary-precision `BigInt` into order-preserving, sign-aware bytes.
+    ///
+    /// ##### Encoding
+    ///
+    /// A sign byte is written first: `0x00` for negative values, `0x01` for zero, `0x02` for
+    /// positive values, so that negative keys sort below zero which sorts below positive keys.
+    ///
+    /// Next comes the magnitude's byte length, encoded with `emit_var_u64` so that more digits
+    /// sorts after fewer, followed by the big-endian magnitude itself with leading zero bytes
+    /// stripped (zero has an empty magnitude).
+    ///
+    /// For negative values, the length and magnitude bytes are bitwise complemented after
+    /// encoding, which inverts their ordering: a longer (more negative) magnitude then sorts
+    /// before a shorter (less negative) one, and for equal lengths a larger magnitude (more
+    /// negative) sorts before a smaller one.
+    pub fn emit_big_int(&mut self, v: &BigInt) -> EncodeResult {
+        let (sign, magnitude) = v.to_bytes_be();
+        match sign {
+            Sign::Minus => {
+                let mut len_buf = MemWriter::new();
+                {
+                    let mut sub = Encoder::new(&mut len_buf);
+                    try!(sub.emit_var_u64(magnitude.len() as u64));
+                }
+                try!(self.writer.write_u8(0x00));
+                for byte in len_buf.unwrap().iter() {
+                    try!(self.writer.write_u8(!*byte));
+                }
+                for byte in magnitude.iter() {
+                    try!(self.writer.write_u8(!*byte));
+                }
+                Ok(())
+            }
+            Sign::NoSign => {
+                try!(self.writer.write_u8(0x01));
+                self.emit_var_u64(0)
+            }
+            Sign::Plus => {
+                try!(self.writer.write_u8(0x02));
+                try!(self.emit_var_u64(magnitude.len() as u64));
+                self.writer.write(magnitude.as_slice())
+            }
+        }
+    }
+
+    /// Encode an unsigned integer into a compact, order-preserving variable number of bytes,
+    /// for use by `CompactUint`.
+    ///
+    /// ##### Encoding
+    ///
+    /// Let `L` be the number of significant bytes of `v` (`L = 0` for `v == 0`, otherwise
+    /// `ceil(bits / 8)` of the magnitude). A single header byte is written whose top `L` bits
+    /// are `1` followed by a `0` bit (a unary length prefix; `L == 8` is the special case where
+    /// all 8 header bits are `1`), followed by the `L`-byte big-endian magnitude.
+    ///
+    /// Since a larger unsigned value always needs at least as many significant bytes, a longer
+    /// encoding carries more leading `1` bits in its header and therefore sorts higher than a
+    /// shorter one, and equal-length encodings compare by magnitude -- so byte order matches
+    /// numeric order. This is more compact than the fixed-width encoding for small magnitudes,
+    /// but never more compact than `emit_var_u64`, whose length prefix is more granular (e.g.
+    /// values below 16 take 2 bytes here and 1 byte there).
+    pub fn emit_compact_uint(&mut self, v: u64) -> EncodeResult {
+        let mut l = 0u;
+        let mut rest = v;
+        while rest > 0 {
+            rest >>= 8;
+            l += 1;
+        }
+
+        let header = if l >= 8 { 0xFFu8 } else { !(0xFFu8 >> l) };
+        try!(self.writer.write_u8(header));
+
+        for i in range(0, l) {
+            let shift = 8 * (l - 1 - i);
+            try!(self.writer.write_u8((v >> shift) as u8));
+        }
+        Ok(())
+    }
 }
 
 pub type EncodeResult = io::IoResult<()>;
 
+impl<'a> Encodable<Encoder<'a>, io::IoError> for BigInt {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        s.emit_big_int(self)
+    }
+}
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for BigUint {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        s.emit_big_int(&self.to_bigint().unwrap())
+    }
+}
+
+/// A wrapper around the fixed-width unsigned integer types (`u8`, `u16`, `u32`, `u64`) that
+/// opts in to `Encoder::emit_compact_uint`'s variable-length encoding instead of the default
+/// fixed-width encoding, shrinking small magnitudes at the cost of a less granular length
+/// prefix than `uint`'s `emit_var_u64`.
+///
+/// #### Usage
+///
+/// ```
+/// # use bytekey::{encode, CompactUint};
+/// assert!(encode(&CompactUint(5u64)).len() < encode(&5u64).len());
+/// ```
+pub struct CompactUint<T>(pub T);
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for CompactUint<u8> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &CompactUint(v) = self;
+        s.emit_compact_uint(v as u64)
+    }
+}
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for CompactUint<u16> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &CompactUint(v) = self;
+        s.emit_compact_uint(v as u64)
+    }
+}
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for CompactUint<u32> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &CompactUint(v) = self;
+        s.emit_compact_uint(v as u64)
+    }
+}
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for CompactUint<u64> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &CompactUint(v) = self;
+        s.emit_compact_uint(v)
+    }
+}
+
+/// A wrapper that validates a float before encoding it.
+///
+/// The default `emit_f32`/`emit_f64` encoding accepts every bit pattern, including every `NAN`
+/// representation, which silently produces a key rather than flagging the caller's mistake --
+/// `NAN` keys are rarely meaningful and typically indicate a bug upstream. Wrapping a float in
+/// `StrictFloat` validates it first: any `NAN` causes `encode` to return an `io::IoError`
+/// instead of a key, and `-0.0` is canonicalized to `+0.0` so the two don't produce distinct
+/// keys for what callers usually consider the same value. The default lenient behavior (a
+/// positive `NAN` sorts after positive infinity, a negative `NAN` before negative infinity, and
+/// `-0.0` sorts just before `+0.0`) is unchanged when the plain `f32`/`f64` encoding is used
+/// instead.
+pub struct StrictFloat<T>(pub T);
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for StrictFloat<f32> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &StrictFloat(v) = self;
+        if v.is_nan() {
+            return Err(io::standard_error(io::OtherIoError));
+        }
+        s.emit_f32(if v == 0.0 { 0.0f32 } else { v })
+    }
+}
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for StrictFloat<f64> {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &StrictFloat(v) = self;
+        if v.is_nan() {
+            return Err(io::standard_error(io::OtherIoError));
+        }
+        s.emit_f64(if v == 0.0 { 0.0f64 } else { v })
+    }
+}
+
+/// A wrapper that flips the sort order of the wrapped value from ascending to descending.
+///
+/// Composite keys frequently need some fields sorted ascending and others descending (e.g. a
+/// "newest first" timestamp alongside an ascending primary key). Wrapping a field's value in
+/// `Descending` produces a key whose byte order is the exact reverse of the unwrapped value's,
+/// and composes naturally inside tuples and structs alongside ascending fields.
+///
+/// ##### Encoding
+///
+/// The wrapped value is encoded normally into a scratch buffer, and every byte of that buffer
+/// (including any length prefixes and terminators) is then bitwise complemented before being
+/// written to the output. Since the unwrapped encoding already orders lexicographically,
+/// complementing every byte reverses the comparison at every position, which gives a correct
+/// total reversal -- including of the variable-length integer, string, and sequence encodings.
+/// The one exception is an encoding that is a proper prefix of another, which still sorts first
+/// after complementing; of the encodings here, only a string with embedded null bytes (e.g.
+/// `"a"` and `"a\0"`) produces one, so such strings do not have their order reversed.
+///
+/// #### Usage
+///
+/// ```
+/// # use bytekey::{encode, Descending};
+/// assert!(encode(&Descending(1u32)) > encode(&Descending(2u32)));
+/// ```
+pub struct Descending<T>(pub T);
+
+impl<'a, T> Encodable<Encoder<'a>, io::IoError> for Descending<T>
+    where T: for<'b> Encodable<Encoder<'b>, io::IoError>
+{
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &Descending(ref v) = self;
+        let mut buf = MemWriter::new();
+        {
+            let mut sub = Encoder::new(&mut buf);
+            try!(v.encode(&mut sub));
+        }
+        for byte in buf.unwrap().iter() {
+            try!(s.writer.write_u8(!*byte));
+        }
+        Ok(())
+    }
+}
+
+/// A wrapper around a raw byte buffer that encodes via `Encoder::emit_bytes` rather than the
+/// sequence encoding `Vec<u8>` would otherwise receive.
+///
+/// #### Usage
+///
+/// ```
+/// # use bytekey::{encode, Bytes};
+/// assert_eq!(vec!(0x2A, 0x00, 0xFF, 0x00, 0x01), encode(&Bytes(vec!(0x2A, 0x00))));
+/// ```
+pub struct Bytes(pub Vec<u8>);
+
+impl<'a> Encodable<Encoder<'a>, io::IoError> for Bytes {
+    fn encode(&self, s: &mut Encoder<'a>) -> EncodeResult {
+        let &Bytes(ref v) = self;
+        s.emit_bytes(v.as_slice())
+    }
+}
+
 impl<'a> serialize::Encoder<io::IoError> for Encoder<'a> {
     fn emit_nil(&mut self) -> EncodeResult { self.writer.write([].as_slice()) }
 
@@ -330,7 +734,15 @@ impl<'a> serialize::Encoder<io::IoError> for Encoder<'a> {
 
     /// Encode an `f32` into sortable bytes.
     ///
-    /// `NaN`s will sort greater than positive infinity. -0.0 will sort directly before +0.0.
+    /// The bit pattern is mapped monotonically so that comparing the output bytes implements
+    /// the IEEE 754-2019 section 5.10 `totalOrder` predicate: every distinct bit pattern,
+    /// including every `NaN` payload and sign, gets a distinct, monotonically ordered key, and
+    /// no bits are lost, so decoding round-trips to the exact input bits. Concretely, negative
+    /// values have every bit flipped (so the largest-magnitude negative value becomes the
+    /// smallest key) while non-negative values have only the sign bit set, before writing the
+    /// result big-endian. `-NaN` therefore sorts below `-INFINITY`, `+NaN` sorts above
+    /// `+INFINITY`, `-0.0` sorts just below `+0.0`, and NaNs with different payloads or
+    /// signaling bits order by their payload.
     ///
     /// See [Hacker's Delight 2nd Edition](http://www.hackersdelight.org/) Section 17-3.
     fn emit_f32(&mut self, v: f32) -> EncodeResult {
@@ -341,7 +753,9 @@ impl<'a> serialize::Encoder<io::IoError> for Encoder<'a> {
 
     /// Encode an `f64` into sortable bytes.
     ///
-    /// `NaN`s will sort greater than positive infinity. -0.0 will sort directly before +0.0.
+    /// Implements the same IEEE 754-2019 section 5.10 `totalOrder` mapping as `emit_f32`,
+    /// widened to 64 bits: every distinct bit pattern, including every `NaN` payload and sign,
+    /// gets a distinct, monotonically ordered key.
     ///
     /// See [Hacker's Delight 2nd Edition](http://www.hackersdelight.org/) Section 17-3.
     fn emit_f64(&mut self, v: f64) -> EncodeResult {
@@ -446,23 +860,25 @@ impl<'a> serialize::Encoder<io::IoError> for Encoder<'a> {
         f(self)
     }
 
-    fn emit_seq(&mut self, _len: uint, _f: |this: &mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
-         fail!("Not yet implemented")
+    fn emit_seq(&mut self, _len: uint, f: |this: &mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
+        try!(f(self));
+        self.writer.write_u8(0x00)
     }
     fn emit_seq_elt(&mut self, _idx: uint, f: |this: &mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
-        // See https://github.com/rust-lang/rust/pull/17504 for why this is implemented currently
-        //fail!("Not yet implemented")
+        try!(self.writer.write_u8(0x01));
         f(self)
     }
 
-    fn emit_map(&mut self, _len: uint, _f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
-        fail!("Not yet implemented")
+    fn emit_map(&mut self, _len: uint, f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
+        try!(f(self));
+        self.writer.write_u8(0x00)
     }
-    fn emit_map_elt_key(&mut self, _idx: uint, _f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
-        fail!("Not yet implemented")
+    fn emit_map_elt_key(&mut self, _idx: uint, f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
+        try!(self.writer.write_u8(0x01));
+        f(self)
     }
-    fn emit_map_elt_val(&mut self, _idx: uint, _f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
-        fail!("Not yet implemented")
+    fn emit_map_elt_val(&mut self, _idx: uint, f: |&mut Encoder<'a>| -> EncodeResult) -> EncodeResult {
+        f(self)
     }
 }
 
@@ -473,9 +889,13 @@ pub mod test {
     extern crate quickcheck_macros;
     extern crate quickcheck;
 
-    use encoder::encode;
-    use std::{u8, u16, i8, i16, f32, f64};
+    use encoder::{encode, encode_desc, encode_ascii, decode_ascii, encode_hex, decode_hex};
+    use encoder::{Bytes, Descending, StrictFloat, Encoder, CompactUint};
+    use serialize::Encodable;
+    use std::{u8, u16, u32, u64, i8, i16, i64, f32, f64};
+    use std::io::MemWriter;
     use std::iter::range_inclusive;
+    use std::mem::transmute;
     use std::num::pow;
     use std::rand::Rng;
 
@@ -552,6 +972,27 @@ pub mod test {
         a.cmp(&b) == encode(&a).cmp(&encode(&b))
     }
 
+    #[test]
+    fn test_compact_uint() {
+        assert_eq!(vec!(0x00), encode(&CompactUint(0u64)));
+        assert_eq!(vec!(0x80, 0x01), encode(&CompactUint(1u64)));
+        assert_eq!(vec!(0x80, 0xFF), encode(&CompactUint(255u64)));
+        assert_eq!(vec!(0xC0, 0x01, 0x00), encode(&CompactUint(256u64)));
+        assert_eq!(
+            vec!(0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+            encode(&CompactUint(pow(2u64, 56))));
+    }
+
+    #[quickcheck]
+    fn check_compact_uint_u32(a: u32, b: u32) -> bool {
+        a.cmp(&b) == encode(&CompactUint(a)).cmp(&encode(&CompactUint(b)))
+    }
+
+    #[quickcheck]
+    fn check_compact_uint_u64(a: u64, b: u64) -> bool {
+        a.cmp(&b) == encode(&CompactUint(a)).cmp(&encode(&CompactUint(b)))
+    }
+
     #[test]
     fn test_i8() {
         let mut previous = encode(&i8::MIN);
@@ -696,6 +1137,63 @@ pub mod test {
         assert!(encode(&f64::INFINITY) < encode(&f64::NAN));
     }
 
+    fn total_order_key_f32(v: f32) -> u32 {
+        let bits = unsafe { transmute::<f32, u32>(v) };
+        let mask = if bits >> 31 & 1 == 1 { u32::MAX } else { 1 << 31 };
+        bits ^ mask
+    }
+
+    fn total_order_key_f64(v: f64) -> u64 {
+        let bits = unsafe { transmute::<f64, u64>(v) };
+        let mask = if bits >> 63 & 1 == 1 { u64::MAX } else { 1 << 63 };
+        bits ^ mask
+    }
+
+    #[test]
+    fn test_f32_total_order_nan() {
+        let neg_quiet_nan: f32 = unsafe { transmute(0xFFC00000u32) };
+        let neg_signaling_nan: f32 = unsafe { transmute(0xFF800001u32) };
+        let pos_quiet_nan: f32 = unsafe { transmute(0x7FC00000u32) };
+        let pos_signaling_nan: f32 = unsafe { transmute(0x7F800001u32) };
+
+        // Negative NaNs sort below -INFINITY; the larger-magnitude (more negative) bit pattern
+        // sorts first.
+        assert!(encode(&neg_quiet_nan) < encode(&f32::NEG_INFINITY));
+        assert!(encode(&neg_signaling_nan) < encode(&f32::NEG_INFINITY));
+        assert!(encode(&neg_quiet_nan) < encode(&neg_signaling_nan));
+
+        // Positive NaNs sort above +INFINITY.
+        assert!(encode(&f32::INFINITY) < encode(&pos_signaling_nan));
+        assert!(encode(&f32::INFINITY) < encode(&pos_quiet_nan));
+        assert!(encode(&pos_signaling_nan) < encode(&pos_quiet_nan));
+    }
+
+    #[test]
+    fn test_f64_total_order_nan() {
+        let neg_quiet_nan: f64 = unsafe { transmute(0xFFF8000000000000u64) };
+        let neg_signaling_nan: f64 = unsafe { transmute(0xFFF0000000000001u64) };
+        let pos_quiet_nan: f64 = unsafe { transmute(0x7FF8000000000000u64) };
+        let pos_signaling_nan: f64 = unsafe { transmute(0x7FF0000000000001u64) };
+
+        assert!(encode(&neg_quiet_nan) < encode(&f64::NEG_INFINITY));
+        assert!(encode(&neg_signaling_nan) < encode(&f64::NEG_INFINITY));
+        assert!(encode(&neg_quiet_nan) < encode(&neg_signaling_nan));
+
+        assert!(encode(&f64::INFINITY) < encode(&pos_signaling_nan));
+        assert!(encode(&f64::INFINITY) < encode(&pos_quiet_nan));
+        assert!(encode(&pos_signaling_nan) < encode(&pos_quiet_nan));
+    }
+
+    #[quickcheck]
+    fn check_f32_total_order(a: f32, b: f32) -> bool {
+        total_order_key_f32(a).cmp(&total_order_key_f32(b)) == encode(&a).cmp(&encode(&b))
+    }
+
+    #[quickcheck]
+    fn check_f64_total_order(a: f64, b: f64) -> bool {
+        total_order_key_f64(a).cmp(&total_order_key_f64(b)) == encode(&a).cmp(&encode(&b))
+    }
+
     #[test]
     fn test_bool() {
         for &(a, b) in vec!((true, true), (true, false), (false, true), (false, false)).iter() {
@@ -718,6 +1216,109 @@ pub mod test {
         a.partial_cmp(&b) == encode(&a).partial_cmp(&encode(&b))
     }
 
+    #[test]
+    fn test_seq() {
+        let empty: Vec<u32> = vec!();
+        assert_eq!(vec!(0x00), encode(&empty));
+        assert_eq!(vec!(0x01, 0x00, 0x00, 0x00, 0x2A, 0x00), encode(&vec!(42u32)));
+
+        assert!(encode(&empty) < encode(&vec!(0u32)));
+        assert!(encode(&vec!(0u32)) < encode(&vec!(0u32, 0u32)));
+        assert!(encode(&vec!(1u32)) < encode(&vec!(2u32)));
+    }
+
+    #[quickcheck]
+    fn check_seq(a: Vec<u32>, b: Vec<u32>) -> bool {
+        a.partial_cmp(&b) == encode(&a).partial_cmp(&encode(&b))
+    }
+
+    #[test]
+    fn test_strict_float() {
+        assert!(StrictFloat(f32::NAN).encode(&mut Encoder::new(&mut MemWriter::new())).is_err());
+        assert!(StrictFloat(f64::NAN).encode(&mut Encoder::new(&mut MemWriter::new())).is_err());
+
+        assert_eq!(encode(&StrictFloat(0.0f32)), encode(&StrictFloat(-0.0f32)));
+        assert_eq!(encode(&StrictFloat(0.0f64)), encode(&StrictFloat(-0.0f64)));
+    }
+
+    #[quickcheck]
+    fn check_strict_float(a: f64, b: f64) -> bool {
+        if a.is_nan() || b.is_nan() {
+            return true;
+        }
+        a.partial_cmp(&b) == encode(&StrictFloat(a)).partial_cmp(&encode(&StrictFloat(b)))
+    }
+
+    #[quickcheck]
+    fn check_descending(a: u32, b: u32) -> bool {
+        a.cmp(&b) == encode(&Descending(b)).cmp(&encode(&Descending(a)))
+    }
+
+    #[quickcheck]
+    fn check_descending_string(a: String, b: String) -> bool {
+        a.cmp(&b) == encode(&Descending(b.clone())).cmp(&encode(&Descending(a.clone())))
+    }
+
+    #[quickcheck]
+    fn check_encode_desc_int(a: i32, b: i32) -> bool {
+        a.cmp(&b) == encode_desc(&b).cmp(&encode_desc(&a))
+    }
+
+    #[quickcheck]
+    fn check_encode_desc_string(a: String, b: String) -> bool {
+        a.cmp(&b) == encode_desc(&b).cmp(&encode_desc(&a))
+    }
+
+    #[quickcheck]
+    fn check_encode_desc_option(a: Option<String>, b: Option<String>) -> bool {
+        a.partial_cmp(&b) == encode_desc(&b).partial_cmp(&encode_desc(&a))
+    }
+
+    #[test]
+    fn test_encode_desc_matches_descending_wrapper() {
+        assert_eq!(encode_desc(&42u32), encode(&Descending(42u32)));
+    }
+
+    #[quickcheck]
+    fn check_descending_tuple(a: (u32, String), b: (u32, String)) -> bool {
+        let desc_a = (a.0, Descending(a.1.clone()));
+        let desc_b = (b.0, Descending(b.1.clone()));
+        let expected = if a.0 != b.0 { a.0.cmp(&b.0) } else { b.1.cmp(&a.1) };
+        expected == encode(&desc_a).cmp(&encode(&desc_b))
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(vec!(0x00, 0x01), encode(&Bytes(vec!())));
+        assert_eq!(vec!(0x2A, 0x00, 0x01), encode(&Bytes(vec!(0x2A))));
+        assert_eq!(vec!(0x00, 0xFF, 0x00, 0x01), encode(&Bytes(vec!(0x00))));
+
+        assert!(encode(&Bytes(vec!())) < encode(&Bytes(vec!(0x00))));
+        assert!(encode(&Bytes(vec!(0x00))) < encode(&Bytes(vec!(0x01))));
+        assert!(encode(&Bytes(vec!(0x01))) < encode(&Bytes(vec!(0x01, 0x00))));
+    }
+
+    #[quickcheck]
+    fn check_bytes(a: Vec<u8>, b: Vec<u8>) -> bool {
+        a.cmp(&b) == encode(&Bytes(a.clone())).cmp(&encode(&Bytes(b.clone())))
+    }
+
+    #[test]
+    fn test_big_int() {
+        use super::num::{BigInt, FromPrimitive};
+
+        let zero: BigInt = FromPrimitive::from_int(0).unwrap();
+        let one: BigInt = FromPrimitive::from_int(1).unwrap();
+        let neg_one: BigInt = FromPrimitive::from_int(-1).unwrap();
+        let big: BigInt = FromPrimitive::from_i64(i64::MAX).unwrap() + one.clone();
+        let neg_big: BigInt = -big.clone();
+
+        assert!(encode(&neg_big) < encode(&neg_one));
+        assert!(encode(&neg_one) < encode(&zero));
+        assert!(encode(&zero) < encode(&one));
+        assert!(encode(&one) < encode(&big));
+    }
+
     #[quickcheck]
     fn check_struct(a: TestStruct, b: TestStruct) -> bool {
         a.partial_cmp(&b) == encode(&a).partial_cmp(&encode(&b))
@@ -733,6 +1334,39 @@ pub mod test {
         a.partial_cmp(&b) == encode(&a).partial_cmp(&encode(&b))
     }
 
+    #[test]
+    fn test_encode_ascii_decode_roundtrip() {
+        assert_eq!(vec!(0x2A, 0x00), decode_ascii(encode_ascii(&(42u8, "")).as_slice()));
+        assert_eq!(vec!(0x2A, 0x00), decode_hex(encode_hex(&(42u8, "")).as_slice()));
+    }
+
+    #[quickcheck]
+    fn check_encode_ascii_order_struct(a: TestStruct, b: TestStruct) -> bool {
+        (encode(&a) < encode(&b)) == (encode_ascii(&a) < encode_ascii(&b))
+    }
+
+    #[quickcheck]
+    fn check_encode_ascii_order_enum(a: TestEnum, b: TestEnum) -> bool {
+        (encode(&a) < encode(&b)) == (encode_ascii(&a) < encode_ascii(&b))
+    }
+
+    #[quickcheck]
+    fn check_encode_ascii_order_tuple(a: (u32, char, String), b: (u32, char, String)) -> bool {
+        (encode(&a) < encode(&b)) == (encode_ascii(&a) < encode_ascii(&b))
+    }
+
+    #[quickcheck]
+    fn check_encode_ascii_roundtrip(a: Vec<u8>) -> bool {
+        let alphabet = super::ASCII_ALPHABET;
+        let s = super::encode_with_alphabet(a.as_slice(), alphabet, 6);
+        a == super::decode_with_alphabet(s.as_slice(), alphabet, 6)
+    }
+
+    #[quickcheck]
+    fn check_encode_hex_order_struct(a: TestStruct, b: TestStruct) -> bool {
+        (encode(&a) < encode(&b)) == (encode_hex(&a) < encode_hex(&b))
+    }
+
     #[deriving(Encodable, Decodable, Clone, Show, PartialEq, PartialOrd)]
     pub struct TestStruct {
         u8_: u8,